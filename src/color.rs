@@ -0,0 +1,60 @@
+use yuv::{YuvRange, YuvStandardMatrix};
+
+/// Transfer (gamma/EOTF) characteristic of the decoded signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorTransfer {
+    /// Standard Bt.709/Bt.601 gamma curve, used by almost all SDR content.
+    Bt709,
+    /// sRGB transfer function.
+    Srgb,
+    /// SMPTE ST 2084 perceptual quantizer, used by HDR10/HDR10+.
+    Pq,
+    /// ARIB STD-B67 hybrid log-gamma, used by broadcast HDR.
+    Hlg,
+}
+
+/// Color primaries (gamut) of the decoded signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorPrimaries {
+    Bt709,
+    Bt601,
+    Bt2020,
+}
+
+/// Colorimetry of a decoded stream: how to map its YUV samples to RGB, and
+/// how to interpret the result once converted.
+///
+/// Previously this was guessed by trying matrix/range combinations until one
+/// "succeeded" — but `yuv_nv12_to_bgra` doesn't fail on a wrong-but-plausible
+/// choice, it just produces washed-out or over-saturated colors. Carrying the
+/// real metadata (or a sensible default derived from frame height when the
+/// stream doesn't specify it) removes that guesswork.
+#[derive(Debug, Clone, Copy)]
+pub struct ColorInfo {
+    pub range: YuvRange,
+    pub matrix: YuvStandardMatrix,
+    pub transfer: ColorTransfer,
+    pub primaries: ColorPrimaries,
+}
+
+impl ColorInfo {
+    /// Bt.709/limited for HD content, Bt.601/limited for SD — the de facto
+    /// defaults most decoders fall back to when a stream omits colorimetry.
+    pub fn default_for_height(height: u32) -> Self {
+        if height >= 720 {
+            Self {
+                range: YuvRange::Limited,
+                matrix: YuvStandardMatrix::Bt709,
+                transfer: ColorTransfer::Bt709,
+                primaries: ColorPrimaries::Bt709,
+            }
+        } else {
+            Self {
+                range: YuvRange::Limited,
+                matrix: YuvStandardMatrix::Bt601,
+                transfer: ColorTransfer::Bt709,
+                primaries: ColorPrimaries::Bt601,
+            }
+        }
+    }
+}
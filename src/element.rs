@@ -1,4 +1,7 @@
-use crate::video::Video;
+use crate::color::{ColorInfo, ColorTransfer};
+use crate::hdr;
+use crate::pixel_format::PixelFormat;
+use crate::video::{FrameLayout, Video};
 #[cfg(target_os = "macos")]
 use core_foundation::{
     base::TCFType,
@@ -7,16 +10,31 @@ use core_foundation::{
     string::CFString,
 };
 #[cfg(target_os = "macos")]
-use core_video::pixel_buffer::{kCVPixelFormatType_420YpCbCr8BiPlanarFullRange, CVPixelBuffer};
+use core_video::pixel_buffer::{
+    kCVPixelFormatType_420YpCbCr8BiPlanarFullRange,
+    kCVPixelFormatType_420YpCbCr8BiPlanarVideoRange, kCVPixelFormatType_420YpCbCr8Planar,
+    kCVPixelFormatType_420YpCbCr8PlanarFullRange, CVPixelBuffer,
+};
 #[cfg(target_os = "macos")]
 use core_video::r#return::kCVReturnSuccess;
 use gpui::{
     Element, ElementId, GlobalElementId, InspectorElementId, IntoElement, LayoutId, Window,
 };
 use std::sync::Arc;
-use yuv::{yuv_nv12_to_bgra, YuvBiPlanarImage, YuvConversionMode, YuvRange, YuvStandardMatrix};
+use yuv::{
+    yuv_i420_to_bgra, yuv_nv12_to_bgra, yuv_nv21_to_bgra, YuvBiPlanarImage, YuvConversionMode,
+    YuvPlanarImage, YuvRange,
+};
 
 /// A video element that implements Element trait similar to GPUI's img element
+///
+/// YUV->RGB conversion always runs on the CPU (`yuv_to_rgb` below), on every
+/// platform other than the macOS zero-copy surface path. A GPU fragment-shader
+/// path was requested in freesurfacemodules/gpui-video#chunk0-4; it isn't
+/// implemented here because `gpui` doesn't yet expose a way to upload
+/// multi-plane YUV textures or bind a custom fragment shader, which that path
+/// needs. Tracked as follow-up work pending that upstream primitive, rather
+/// than landing as a public builder that can never do anything.
 pub struct VideoElement {
     video: Video,
     display_width: Option<gpui::Pixels>,
@@ -109,6 +127,90 @@ impl VideoElement {
         )
     }
 
+    /// Replicate the last visible row/column of a plane out to the coded edge.
+    ///
+    /// Some decoders leave whatever was previously in the padding between the
+    /// visible rect and the coded buffer's edge, which can read as garbage if a
+    /// downstream scaler samples a tap that lands just past the visible edge.
+    /// Extending by replication keeps those taps well-defined; the bottom-right
+    /// corner falls out naturally since the row extension copies the
+    /// already-column-extended last visible row.
+    ///
+    /// `sample_size` is the byte width of one logical sample in this plane:
+    /// 1 for a single-component plane, but 2 for an interleaved chroma plane
+    /// (U and V packed together). Column replication copies whole samples,
+    /// not individual bytes — copying single bytes across an interleaved
+    /// plane's padding would misalign U/V pairs and tint the padded strip.
+    fn extend_plane_edges(
+        plane: &mut [u8],
+        stride: usize,
+        coded_height: usize,
+        visible_width: usize,
+        visible_height: usize,
+        sample_size: usize,
+    ) {
+        if visible_width == 0 || visible_height == 0 {
+            return;
+        }
+
+        if stride > visible_width {
+            let last_sample_start = visible_width - sample_size;
+            for row in 0..visible_height {
+                let row_start = row * stride;
+                let mut col = visible_width;
+                while col < stride {
+                    // The padding width isn't guaranteed to be a multiple of
+                    // `sample_size` (e.g. an odd byte of slack after an
+                    // interleaved chroma plane), so the last copy in a row may
+                    // need truncating to avoid writing past `stride`.
+                    let copy_len = sample_size.min(stride - col);
+                    plane.copy_within(
+                        row_start + last_sample_start..row_start + last_sample_start + copy_len,
+                        row_start + col,
+                    );
+                    col += sample_size;
+                }
+            }
+        }
+
+        if coded_height > visible_height {
+            let last_row_start = (visible_height - 1) * stride;
+            let (head, tail) = plane.split_at_mut(visible_height * stride);
+            let last_row = &head[last_row_start..last_row_start + stride];
+            for row in tail.chunks_mut(stride) {
+                row.copy_from_slice(&last_row[..row.len()]);
+            }
+        }
+    }
+
+    /// 10-bit path: upload P010/I010 samples into a half-float (RGBA16F)
+    /// surface so none of the extra precision over 8-bit is lost. Returns
+    /// true if it painted.
+    ///
+    /// Requires the windowing backend to expose half-float texture
+    /// allocation through gpui, which it does not yet do; until then this
+    /// always falls back so `paint()` can call it unconditionally for
+    /// high-bit-depth frames and rely on `convert_high_bit_depth` otherwise.
+    fn try_paint_half_float_hdr(
+        &mut self,
+        _window: &mut Window,
+        _bounds: gpui::Bounds<gpui::Pixels>,
+        _yuv_data: &[u8],
+        _layout: &FrameLayout,
+        _color: &ColorInfo,
+    ) -> bool {
+        if !hdr::half_float_textures_available() {
+            return false;
+        }
+
+        // TODO: upload `_yuv_data`'s planes as RGBA16F textures (scaling the
+        // 10-bit samples to [0, 1] rather than downshifting to 8 bits) and
+        // paint them, applying `_color.transfer`'s PQ/HLG EOTF in the same
+        // shader pass used for SDR content, once gpui exposes half-float
+        // texture allocation.
+        false
+    }
+
     /// Paint using GPUI sprite atlas with a BGRA buffer, while evicting the previous frame's texture.
     fn paint_render_image(
         &mut self,
@@ -156,25 +258,47 @@ impl VideoElement {
         }
     }
 
-    /// macOS-only: Try to render NV12 via CVPixelBuffer and paint_surface. Returns true if painted.
+    /// macOS-only: try to render via a zero-copy CVPixelBuffer/IOSurface and
+    /// `paint_surface`. Returns true if painted.
+    ///
+    /// Prefers the CVPixelBuffer layout (bi-planar NV12-order, or three-plane
+    /// planar) matching the source so the copy is a straight memcpy (NV21
+    /// still swaps each chroma pair into NV12 order); if
+    /// CoreVideo can't allocate or validate that layout, falls back to the
+    /// other one and interleaves/deinterleaves the chroma during the copy
+    /// instead of giving up the zero-copy path entirely.
     #[cfg(target_os = "macos")]
     fn try_paint_surface_macos(
         &mut self,
         window: &mut Window,
         bounds: gpui::Bounds<gpui::Pixels>,
         yuv_data: &[u8],
-        frame_width: u32,
-        frame_height: u32,
+        layout: &FrameLayout,
+        color: &ColorInfo,
     ) -> bool {
-        let width = frame_width as usize;
-        let height = frame_height as usize;
-        let y_size = width * height;
-        let uv_size = (width * height) / 2;
-        if yuv_data.len() < y_size + uv_size || width == 0 || height == 0 {
+        // 10-bit content has no zero-copy path today; it's downshifted to
+        // 8-bit and CPU-converted instead.
+        if layout.format.is_high_bit_depth() {
+            return false;
+        }
+        if layout.visible_rect.width == 0 || layout.visible_rect.height == 0 {
             return false;
         }
 
-        // Build attributes: Metal compatible + backed by IOSurface
+        let is_biplanar_source = matches!(layout.format, PixelFormat::Nv12 | PixelFormat::Nv21);
+        if is_biplanar_source {
+            self.paint_biplanar_surface(window, bounds, yuv_data, layout, color)
+                || self.paint_planar_surface(window, bounds, yuv_data, layout, color)
+        } else {
+            self.paint_planar_surface(window, bounds, yuv_data, layout, color)
+                || self.paint_biplanar_surface(window, bounds, yuv_data, layout, color)
+        }
+    }
+
+    /// Build the Metal-compatible, IOSurface-backed attributes dictionary
+    /// shared by every CVPixelBuffer this module allocates.
+    #[cfg(target_os = "macos")]
+    fn surface_attrs() -> CFMutableDictionary<CFString, core_foundation::base::CFType> {
         let mut attrs: CFMutableDictionary<CFString, core_foundation::base::CFType> =
             CFMutableDictionary::new();
         attrs.add(
@@ -187,21 +311,43 @@ impl VideoElement {
             &core_video::pixel_buffer::CVPixelBufferKeys::IOSurfaceProperties.into(),
             &empty_iosurf.as_CFType(),
         );
+        attrs
+    }
 
+    /// Render into a two-plane (Y + interleaved UV) CVPixelBuffer in NV12's
+    /// U-before-V order. Accepts any of the four 8-bit 4:2:0 source layouts,
+    /// swapping or interleaving chroma bytes during the copy as needed so
+    /// NV21/I420/YV12 content still reaches this fast path.
+    #[cfg(target_os = "macos")]
+    fn paint_biplanar_surface(
+        &mut self,
+        window: &mut Window,
+        bounds: gpui::Bounds<gpui::Pixels>,
+        yuv_data: &[u8],
+        layout: &FrameLayout,
+        color: &ColorInfo,
+    ) -> bool {
+        let cv_pixel_format = match color.range {
+            YuvRange::Full => kCVPixelFormatType_420YpCbCr8BiPlanarFullRange,
+            YuvRange::Limited => kCVPixelFormatType_420YpCbCr8BiPlanarVideoRange,
+        };
+
+        let visible = layout.visible_rect;
+        let vis_width = visible.width as usize;
+        let vis_height = visible.height as usize;
+
+        let attrs = Self::surface_attrs();
         let Ok(pixel_buffer) = CVPixelBuffer::new(
-            kCVPixelFormatType_420YpCbCr8BiPlanarFullRange,
-            width,
-            height,
+            cv_pixel_format,
+            vis_width,
+            vis_height,
             Some(&attrs.to_immutable()),
         ) else {
             return false;
         };
 
-        // Validate pixel buffer layout before unsafe copies; fall back if anything is off.
         let pf = pixel_buffer.get_pixel_format();
-        if pf != kCVPixelFormatType_420YpCbCr8BiPlanarFullRange
-            || !pixel_buffer.is_planar()
-            || pixel_buffer.get_plane_count() != 2
+        if pf != cv_pixel_format || !pixel_buffer.is_planar() || pixel_buffer.get_plane_count() != 2
         {
             return false;
         }
@@ -209,15 +355,14 @@ impl VideoElement {
         let y_h = pixel_buffer.get_height_of_plane(0);
         let uv_w = pixel_buffer.get_width_of_plane(1);
         let uv_h = pixel_buffer.get_height_of_plane(1);
-        let y_stride = pixel_buffer.get_bytes_per_row_of_plane(0);
-        let uv_stride = pixel_buffer.get_bytes_per_row_of_plane(1);
-
-        if !(y_w == width
-            && y_h == height
-            && uv_w == width / 2
-            && uv_h == height / 2
-            && y_stride >= width
-            && uv_stride >= width)
+        let dst_y_stride = pixel_buffer.get_bytes_per_row_of_plane(0);
+        let dst_uv_stride = pixel_buffer.get_bytes_per_row_of_plane(1);
+        if !(y_w == vis_width
+            && y_h == vis_height
+            && uv_w == vis_width / 2
+            && uv_h == vis_height / 2
+            && dst_y_stride >= vis_width
+            && dst_uv_stride >= vis_width)
         {
             return false;
         }
@@ -225,112 +370,616 @@ impl VideoElement {
         if pixel_buffer.lock_base_address(0) != kCVReturnSuccess {
             return false;
         }
-        unsafe {
+
+        let vis_x = visible.x as usize;
+        let vis_y = visible.y as usize;
+        let y_stride = layout.plane_strides[0];
+        let y_size = y_stride * layout.coded_height as usize;
+        let chroma_h = layout.coded_height as usize / 2;
+
+        let copied = unsafe {
             let y_dst = pixel_buffer.get_base_address_of_plane(0) as *mut u8;
             let uv_dst = pixel_buffer.get_base_address_of_plane(1) as *mut u8;
 
-            // Copy Y plane row-wise respecting stride
-            for row in 0..height {
-                let src_off = row * width;
-                let dst_off = row * y_stride;
-                std::ptr::copy_nonoverlapping(
-                    yuv_data.as_ptr().add(src_off),
-                    y_dst.add(dst_off),
-                    width,
-                );
-            }
-            // Copy UV plane
-            for row in 0..(height / 2) {
-                let src_off = y_size + row * width;
-                let dst_off = row * uv_stride;
-                std::ptr::copy_nonoverlapping(
-                    yuv_data.as_ptr().add(src_off),
-                    uv_dst.add(dst_off),
-                    width,
-                );
+            if !Self::copy_plane_rows(yuv_data, y_stride, vis_x, vis_y, y_dst, dst_y_stride, vis_width, vis_height) {
+                pixel_buffer.unlock_base_address(0);
+                return false;
             }
+
+            let ok = match layout.format {
+                PixelFormat::Nv12 | PixelFormat::Nv21 => {
+                    let uv_stride = layout.plane_strides[1];
+                    let swapped = layout.format == PixelFormat::Nv21;
+                    let mut ok = true;
+                    for row in 0..(vis_height / 2) {
+                        let src_off = y_size + (vis_y / 2 + row) * uv_stride + (vis_x / 2) * 2;
+                        if src_off + vis_width > yuv_data.len() {
+                            ok = false;
+                            break;
+                        }
+                        let dst_row = uv_dst.add(row * dst_uv_stride);
+                        if swapped {
+                            // NV21 stores V before U; swap each pair on the way
+                            // in so the destination keeps NV12's U-then-V order.
+                            for col in 0..(vis_width / 2) {
+                                let v = *yuv_data.as_ptr().add(src_off + col * 2);
+                                let u = *yuv_data.as_ptr().add(src_off + col * 2 + 1);
+                                *dst_row.add(col * 2) = u;
+                                *dst_row.add(col * 2 + 1) = v;
+                            }
+                        } else {
+                            std::ptr::copy_nonoverlapping(
+                                yuv_data.as_ptr().add(src_off),
+                                dst_row,
+                                vis_width,
+                            );
+                        }
+                    }
+                    ok
+                }
+                PixelFormat::I420 | PixelFormat::Yv12 => {
+                    let (u_off, u_stride, v_off, v_stride) =
+                        Self::planar_chroma_offsets(layout.format, y_size, layout.plane_strides, chroma_h);
+                    let mut ok = true;
+                    for row in 0..(vis_height / 2) {
+                        let u_row_off = u_off + (vis_y / 2 + row) * u_stride + vis_x / 2;
+                        let v_row_off = v_off + (vis_y / 2 + row) * v_stride + vis_x / 2;
+                        if u_row_off + vis_width / 2 > yuv_data.len() || v_row_off + vis_width / 2 > yuv_data.len() {
+                            ok = false;
+                            break;
+                        }
+                        let dst_row = uv_dst.add(row * dst_uv_stride);
+                        for col in 0..(vis_width / 2) {
+                            *dst_row.add(col * 2) = *yuv_data.as_ptr().add(u_row_off + col);
+                            *dst_row.add(col * 2 + 1) = *yuv_data.as_ptr().add(v_row_off + col);
+                        }
+                    }
+                    ok
+                }
+                _ => false,
+            };
+            ok
+        };
+
+        let _ = pixel_buffer.unlock_base_address(0);
+        if !copied {
+            return false;
+        }
+
+        let dest_bounds = self.fitted_bounds(bounds, visible.width, visible.height);
+        window.paint_surface(dest_bounds, pixel_buffer);
+        true
+    }
+
+    /// Render into a three-plane (Y, U, V) CVPixelBuffer. Accepts any of the
+    /// four 8-bit 4:2:0 source layouts, deinterleaving bi-planar chroma into
+    /// separate U/V planes during the copy as needed.
+    #[cfg(target_os = "macos")]
+    fn paint_planar_surface(
+        &mut self,
+        window: &mut Window,
+        bounds: gpui::Bounds<gpui::Pixels>,
+        yuv_data: &[u8],
+        layout: &FrameLayout,
+        color: &ColorInfo,
+    ) -> bool {
+        let cv_pixel_format = match color.range {
+            YuvRange::Full => kCVPixelFormatType_420YpCbCr8PlanarFullRange,
+            YuvRange::Limited => kCVPixelFormatType_420YpCbCr8Planar,
+        };
+
+        let visible = layout.visible_rect;
+        let vis_width = visible.width as usize;
+        let vis_height = visible.height as usize;
+
+        let attrs = Self::surface_attrs();
+        let Ok(pixel_buffer) = CVPixelBuffer::new(
+            cv_pixel_format,
+            vis_width,
+            vis_height,
+            Some(&attrs.to_immutable()),
+        ) else {
+            return false;
+        };
+
+        let pf = pixel_buffer.get_pixel_format();
+        if pf != cv_pixel_format || !pixel_buffer.is_planar() || pixel_buffer.get_plane_count() != 3
+        {
+            return false;
+        }
+        let y_w = pixel_buffer.get_width_of_plane(0);
+        let y_h = pixel_buffer.get_height_of_plane(0);
+        let u_w = pixel_buffer.get_width_of_plane(1);
+        let u_h = pixel_buffer.get_height_of_plane(1);
+        let v_w = pixel_buffer.get_width_of_plane(2);
+        let v_h = pixel_buffer.get_height_of_plane(2);
+        let dst_y_stride = pixel_buffer.get_bytes_per_row_of_plane(0);
+        let dst_u_stride = pixel_buffer.get_bytes_per_row_of_plane(1);
+        let dst_v_stride = pixel_buffer.get_bytes_per_row_of_plane(2);
+        if !(y_w == vis_width
+            && y_h == vis_height
+            && u_w == vis_width / 2
+            && u_h == vis_height / 2
+            && v_w == vis_width / 2
+            && v_h == vis_height / 2
+            && dst_y_stride >= vis_width
+            && dst_u_stride >= vis_width / 2
+            && dst_v_stride >= vis_width / 2)
+        {
+            return false;
+        }
+
+        if pixel_buffer.lock_base_address(0) != kCVReturnSuccess {
+            return false;
         }
+
+        let vis_x = visible.x as usize;
+        let vis_y = visible.y as usize;
+        let y_stride = layout.plane_strides[0];
+        let y_size = y_stride * layout.coded_height as usize;
+        let chroma_h = layout.coded_height as usize / 2;
+
+        let copied = unsafe {
+            let y_dst = pixel_buffer.get_base_address_of_plane(0) as *mut u8;
+            let u_dst = pixel_buffer.get_base_address_of_plane(1) as *mut u8;
+            let v_dst = pixel_buffer.get_base_address_of_plane(2) as *mut u8;
+
+            if !Self::copy_plane_rows(yuv_data, y_stride, vis_x, vis_y, y_dst, dst_y_stride, vis_width, vis_height) {
+                pixel_buffer.unlock_base_address(0);
+                return false;
+            }
+
+            let ok = match layout.format {
+                PixelFormat::I420 | PixelFormat::Yv12 => {
+                    let (u_off, u_stride, v_off, v_stride) =
+                        Self::planar_chroma_offsets(layout.format, y_size, layout.plane_strides, chroma_h);
+                    let mut ok = true;
+                    for row in 0..(vis_height / 2) {
+                        let u_row_off = u_off + (vis_y / 2 + row) * u_stride + vis_x / 2;
+                        let v_row_off = v_off + (vis_y / 2 + row) * v_stride + vis_x / 2;
+                        if u_row_off + vis_width / 2 > yuv_data.len() || v_row_off + vis_width / 2 > yuv_data.len() {
+                            ok = false;
+                            break;
+                        }
+                        std::ptr::copy_nonoverlapping(
+                            yuv_data.as_ptr().add(u_row_off),
+                            u_dst.add(row * dst_u_stride),
+                            vis_width / 2,
+                        );
+                        std::ptr::copy_nonoverlapping(
+                            yuv_data.as_ptr().add(v_row_off),
+                            v_dst.add(row * dst_v_stride),
+                            vis_width / 2,
+                        );
+                    }
+                    ok
+                }
+                PixelFormat::Nv12 | PixelFormat::Nv21 => {
+                    let uv_stride = layout.plane_strides[1];
+                    let swapped = layout.format == PixelFormat::Nv21;
+                    let mut ok = true;
+                    for row in 0..(vis_height / 2) {
+                        let src_off = y_size + (vis_y / 2 + row) * uv_stride + (vis_x / 2) * 2;
+                        if src_off + vis_width > yuv_data.len() {
+                            ok = false;
+                            break;
+                        }
+                        let u_row = u_dst.add(row * dst_u_stride);
+                        let v_row = v_dst.add(row * dst_v_stride);
+                        for col in 0..(vis_width / 2) {
+                            let first = *yuv_data.as_ptr().add(src_off + col * 2);
+                            let second = *yuv_data.as_ptr().add(src_off + col * 2 + 1);
+                            let (u, v) = if swapped { (second, first) } else { (first, second) };
+                            *u_row.add(col) = u;
+                            *v_row.add(col) = v;
+                        }
+                    }
+                    ok
+                }
+                _ => false,
+            };
+            ok
+        };
+
         let _ = pixel_buffer.unlock_base_address(0);
+        if !copied {
+            return false;
+        }
 
-        let dest_bounds = self.fitted_bounds(bounds, frame_width, frame_height);
+        let dest_bounds = self.fitted_bounds(bounds, visible.width, visible.height);
         window.paint_surface(dest_bounds, pixel_buffer);
         true
     }
 
-    /// Convert NV12 YUV data to RGB using optimized yuvutils-rs
-    fn yuv_to_rgb(&self, yuv_data: &[u8], width: u32, height: u32) -> Vec<u8> {
-        let width_usize = width as usize;
-        let height_usize = height as usize;
-        let y_size = width_usize * height_usize;
-        let uv_size = (width_usize * height_usize) / 2;
+    /// Copy the Y plane's visible rows from `src` into a destination plane at
+    /// `dst`, honoring both the source's real stride and the destination's
+    /// own bytes-per-row. Returns false as soon as a row would run past the
+    /// end of `src`; rows before it have already been copied.
+    #[cfg(target_os = "macos")]
+    unsafe fn copy_plane_rows(
+        src: &[u8],
+        src_stride: usize,
+        vis_x: usize,
+        vis_y: usize,
+        dst: *mut u8,
+        dst_stride: usize,
+        vis_width: usize,
+        vis_height: usize,
+    ) -> bool {
+        for row in 0..vis_height {
+            let src_off = (vis_y + row) * src_stride + vis_x;
+            if src_off + vis_width > src.len() {
+                return false;
+            }
+            std::ptr::copy_nonoverlapping(src.as_ptr().add(src_off), dst.add(row * dst_stride), vis_width);
+        }
+        true
+    }
+
+    /// Byte offset and stride of the logical U and V chroma planes within a
+    /// contiguous I420/YV12 buffer. I420 stores planes in Y, U, V order; YV12
+    /// stores Y, V, U — this maps either physical layout back to logical
+    /// (u_off, u_stride, v_off, v_stride) so callers never special-case it.
+    #[cfg(target_os = "macos")]
+    fn planar_chroma_offsets(
+        format: PixelFormat,
+        y_size: usize,
+        plane_strides: [usize; 3],
+        chroma_h: usize,
+    ) -> (usize, usize, usize, usize) {
+        match format {
+            PixelFormat::I420 => (
+                y_size,
+                plane_strides[1],
+                y_size + plane_strides[1] * chroma_h,
+                plane_strides[2],
+            ),
+            PixelFormat::Yv12 => (
+                y_size + plane_strides[1] * chroma_h,
+                plane_strides[2],
+                y_size,
+                plane_strides[1],
+            ),
+            _ => unreachable!("planar_chroma_offsets only handles I420/YV12"),
+        }
+    }
+
+    /// Convert decoded YUV data to RGB using optimized yuvutils-rs, dispatching
+    /// on the frame's actual pixel format instead of assuming NV12.
+    fn yuv_to_rgb(yuv_data: &[u8], layout: &FrameLayout, color: &ColorInfo) -> Vec<u8> {
+        match layout.format {
+            PixelFormat::Nv12 | PixelFormat::Nv21 => Self::convert_biplanar(yuv_data, layout, color),
+            PixelFormat::I420 | PixelFormat::Yv12 => Self::convert_planar(yuv_data, layout, color),
+            PixelFormat::P010 | PixelFormat::I010 => {
+                Self::convert_high_bit_depth(yuv_data, layout, color)
+            }
+        }
+    }
+
+    /// Convert an NV12/NV21 (one Y plane, one interleaved chroma plane) frame.
+    fn convert_biplanar(yuv_data: &[u8], layout: &FrameLayout, color: &ColorInfo) -> Vec<u8> {
+        let descriptor = layout.format.descriptor();
+        let coded_width = layout.coded_width;
+        let coded_height = layout.coded_height;
+        let coded_w = coded_width as usize;
+        let coded_h = coded_height as usize;
+        let y_stride = layout.plane_strides[0];
+        let uv_stride = layout.plane_strides[1];
+        let visible = layout.visible_rect;
+        let vis_w = visible.width as usize;
+        let vis_h = visible.height as usize;
+
+        let chroma_h = coded_h / descriptor.vertical_subsampling as usize;
+        let y_size = y_stride * coded_h;
+        let uv_size = uv_stride * chroma_h;
 
-        if yuv_data.len() < y_size + uv_size {
-            // Not enough data, return black frame
-            return vec![0; width_usize * height_usize * 4];
+        if yuv_data.len() < y_size + uv_size || vis_w == 0 || vis_h == 0 {
+            // Not enough data, return a black frame sized to what we'd display.
+            return vec![0; vis_w * vis_h * 4];
         }
 
-        // Split NV12 data into Y and UV planes
-        let y_plane = &yuv_data[..y_size];
-        let uv_plane = &yuv_data[y_size..y_size + uv_size];
+        // The interleaved chroma plane packs `components_per_plane[1]`
+        // components per subsampled sample, which for 4:2:0 bi-planar layouts
+        // cancels out the horizontal subsampling and leaves its byte width
+        // equal to the luma width.
+        let uv_width_bytes =
+            (vis_w / descriptor.horizontal_subsampling as usize) * descriptor.components_per_plane[1];
+        let vis_chroma_h = vis_h / descriptor.vertical_subsampling as usize;
+
+        // Work on owned copies of the planes so we can replicate the edge
+        // pixels into any padding before handing them to the converter.
+        let mut y_plane = yuv_data[..y_size].to_vec();
+        let mut uv_plane = yuv_data[y_size..y_size + uv_size].to_vec();
+        if coded_w > vis_w || coded_h > vis_h {
+            Self::extend_plane_edges(
+                &mut y_plane,
+                y_stride,
+                coded_h,
+                vis_w,
+                vis_h,
+                descriptor.components_per_plane[0],
+            );
+            Self::extend_plane_edges(
+                &mut uv_plane,
+                uv_stride,
+                chroma_h,
+                uv_width_bytes,
+                vis_chroma_h,
+                descriptor.components_per_plane[1],
+            );
+        }
 
-        // Create YuvBiPlanarImage structure for NV12 data
         let yuv_bi_planar = YuvBiPlanarImage {
-            y_plane,
-            y_stride: width,
-            uv_plane,
-            uv_stride: width, // NV12 UV stride is same as width
-            width,
-            height,
+            y_plane: &y_plane,
+            y_stride: y_stride as u32,
+            uv_plane: &uv_plane,
+            uv_stride: uv_stride as u32,
+            width: coded_width,
+            height: coded_height,
         };
 
-        // Prepare output RGB buffer (BGRA format)
-        let mut bgra = vec![0u8; width_usize * height_usize * 4];
-        let rgba_stride = width * 4;
+        // Prepare output RGB buffer (BGRA format), sized to the coded buffer so
+        // the conversion can use the real strides; we crop to `visible` after.
+        let mut bgra = vec![0u8; coded_w * coded_h * 4];
+        let rgba_stride = coded_width * 4;
 
-        // Use yuvutils-rs optimized NV12 to RGB conversion
-        // Try Bt709 first (HD standard) with full range
-        if yuv_nv12_to_bgra(
+        let convert = if layout.format == PixelFormat::Nv21 {
+            yuv_nv21_to_bgra
+        } else {
+            yuv_nv12_to_bgra
+        };
+
+        // One deterministic call using the stream's real colorimetry — no more
+        // trying matrix/range combinations until one "looks right".
+        let converted = convert(
             &yuv_bi_planar,
             &mut bgra,
             rgba_stride,
-            YuvRange::Full,              // Try full range first
-            YuvStandardMatrix::Bt709,    // HD standard
-            YuvConversionMode::Balanced, // Use balanced conversion mode (default)
+            color.range,
+            color.matrix,
+            YuvConversionMode::Balanced,
         )
-        .is_ok()
-        {
-            return bgra;
+        .is_ok();
+
+        if !converted {
+            // Final fallback to black frame on conversion error.
+            return vec![0; vis_w * vis_h * 4];
         }
 
-        // Try Bt709 with limited range
-        if yuv_nv12_to_bgra(
-            &yuv_bi_planar,
+        Self::crop_bgra(&bgra, coded_w, visible)
+    }
+
+    /// Convert an I420/YV12 (three fully separate planes) frame.
+    fn convert_planar(yuv_data: &[u8], layout: &FrameLayout, color: &ColorInfo) -> Vec<u8> {
+        let descriptor = layout.format.descriptor();
+        let coded_width = layout.coded_width;
+        let coded_height = layout.coded_height;
+        let coded_w = coded_width as usize;
+        let coded_h = coded_height as usize;
+        let visible = layout.visible_rect;
+        let vis_w = visible.width as usize;
+        let vis_h = visible.height as usize;
+
+        let p0_stride = layout.plane_strides[0];
+        let p1_stride = layout.plane_strides[1];
+        let p2_stride = layout.plane_strides[2];
+        let chroma_h = coded_h / descriptor.vertical_subsampling as usize;
+
+        let p0_size = p0_stride * coded_h;
+        let p1_size = p1_stride * chroma_h;
+        let p2_size = p2_stride * chroma_h;
+
+        if yuv_data.len() < p0_size + p1_size + p2_size || vis_w == 0 || vis_h == 0 {
+            return vec![0; vis_w * vis_h * 4];
+        }
+
+        // Each chroma plane packs one component per subsampled sample, so its
+        // visible byte width is just the subsampled visible width.
+        let vis_chroma_w =
+            (vis_w / descriptor.horizontal_subsampling as usize) * descriptor.components_per_plane[1];
+        let vis_chroma_h = vis_h / descriptor.vertical_subsampling as usize;
+
+        // Work on owned copies of the planes so we can replicate the edge
+        // pixels into any padding before handing them to the converter, same
+        // as `convert_biplanar` does for its interleaved chroma plane.
+        let mut y_plane = yuv_data[..p0_size].to_vec();
+        let mut plane1 = yuv_data[p0_size..p0_size + p1_size].to_vec();
+        let mut plane2 = yuv_data[p0_size + p1_size..p0_size + p1_size + p2_size].to_vec();
+        if coded_w > vis_w || coded_h > vis_h {
+            Self::extend_plane_edges(
+                &mut y_plane,
+                p0_stride,
+                coded_h,
+                vis_w,
+                vis_h,
+                descriptor.components_per_plane[0],
+            );
+            Self::extend_plane_edges(
+                &mut plane1,
+                p1_stride,
+                chroma_h,
+                vis_chroma_w,
+                vis_chroma_h,
+                descriptor.components_per_plane[1],
+            );
+            Self::extend_plane_edges(
+                &mut plane2,
+                p2_stride,
+                chroma_h,
+                vis_chroma_w,
+                vis_chroma_h,
+                descriptor.components_per_plane[2],
+            );
+        }
+
+        // I420 stores planes in Y, U, V order; YV12 stores Y, V, U. Pick the
+        // right physical plane for each chroma channel so downstream code
+        // never has to special-case the difference again.
+        let (u_plane, u_stride, v_plane, v_stride) = match layout.format {
+            PixelFormat::I420 => (&plane1, p1_stride, &plane2, p2_stride),
+            PixelFormat::Yv12 => (&plane2, p2_stride, &plane1, p1_stride),
+            _ => unreachable!("convert_planar only handles I420/YV12"),
+        };
+
+        let yuv_planar = YuvPlanarImage {
+            y_plane: &y_plane,
+            y_stride: p0_stride as u32,
+            u_plane,
+            u_stride: u_stride as u32,
+            v_plane,
+            v_stride: v_stride as u32,
+            width: coded_width,
+            height: coded_height,
+        };
+
+        let mut bgra = vec![0u8; coded_w * coded_h * 4];
+        let rgba_stride = coded_width * 4;
+
+        let converted = yuv_i420_to_bgra(
+            &yuv_planar,
             &mut bgra,
             rgba_stride,
-            YuvRange::Limited,           // Limited range
-            YuvStandardMatrix::Bt709,    // HD standard
-            YuvConversionMode::Balanced, // Use balanced conversion mode (default)
+            color.range,
+            color.matrix,
+            YuvConversionMode::Balanced,
         )
-        .is_ok()
-        {
-            return bgra;
+        .is_ok();
+
+        if !converted {
+            return vec![0; vis_w * vis_h * 4];
         }
 
-        // Fallback to Bt601 (SD standard)
-        match yuv_nv12_to_bgra(
-            &yuv_bi_planar,
-            &mut bgra,
-            rgba_stride,
-            YuvRange::Limited,
-            YuvStandardMatrix::Bt601,
-            YuvConversionMode::Balanced, // Use balanced conversion mode (default)
-        ) {
-            Ok(_) => bgra,
-            Err(_) => {
-                // Final fallback to black frame on conversion error
-                vec![0; width_usize * height_usize * 4]
+        Self::crop_bgra(&bgra, coded_w, visible)
+    }
+
+    /// Convert a 10-bit P010/I010 frame by downshifting each sample to 8 bits
+    /// and reusing the matching 8-bit conversion path. This trades away the
+    /// extra precision but keeps HDR/10-bit content watchable everywhere.
+    ///
+    /// Luma samples on PQ/HLG streams are routed through their EOTF and a
+    /// tonemap before re-encoding to 8 bits, rather than a naive bit-shift, so
+    /// HDR10/HLG content doesn't come out looking like it was shot in Bt.709
+    /// with an SDR gamma curve. Chroma gets no tonemap: a YCbCr signal
+    /// doesn't have a meaningful per-channel tonemap the way luma does. It is
+    /// a plain shift, except that on PQ/HLG streams limited-range chroma is
+    /// rescaled to full range to match the re-encoded luma. Each
+    /// raw sample is right-justified via `descriptor.shift` first (P010 packs
+    /// its 10 bits MSB-justified; I010 is already LSB-justified) before
+    /// either of those paths sees it.
+    ///
+    /// This is the path `paint()` falls back to when `try_paint_half_float_hdr`
+    /// can't upload half-float textures, which today is always.
+    fn convert_high_bit_depth(yuv_data: &[u8], layout: &FrameLayout, color: &ColorInfo) -> Vec<u8> {
+        let descriptor = layout.format.descriptor();
+        let eight_bit_format = match layout.format {
+            PixelFormat::P010 => PixelFormat::Nv12,
+            PixelFormat::I010 => PixelFormat::I420,
+            _ => unreachable!("convert_high_bit_depth only handles P010/I010"),
+        };
+
+        let coded_h = layout.coded_height as usize;
+        let visible = layout.visible_rect;
+        let vis_w = visible.width as usize;
+        let vis_h = visible.height as usize;
+
+        let mut plane_rows = [0usize; 3];
+        let mut total_bytes = 0usize;
+        for plane in 0..descriptor.plane_count {
+            plane_rows[plane] = if plane == 0 {
+                coded_h
+            } else {
+                coded_h / descriptor.vertical_subsampling as usize
+            };
+            total_bytes += layout.plane_strides[plane] * plane_rows[plane];
+        }
+
+        if yuv_data.len() < total_bytes || vis_w == 0 || vis_h == 0 {
+            // Not enough data (e.g. a decoder handing back a truncated frame),
+            // return a black frame sized to what we'd display instead of
+            // panicking on an out-of-bounds index below, matching the
+            // 8-bit paths' short-buffer fallback.
+            return vec![0; vis_w * vis_h * 4];
+        }
+
+        let force_chroma_full_range = matches!(color.transfer, ColorTransfer::Pq | ColorTransfer::Hlg);
+        let mut downshifted = Vec::with_capacity(yuv_data.len() / descriptor.bytes_per_sample);
+        let mut plane_strides = [0usize; 3];
+        let mut offset = 0usize;
+        for plane in 0..descriptor.plane_count {
+            let rows = plane_rows[plane];
+            let stride_bytes = layout.plane_strides[plane];
+            let samples_per_row = stride_bytes / descriptor.bytes_per_sample;
+            plane_strides[plane] = samples_per_row;
+
+            for row in 0..rows {
+                let row_start = offset + row * stride_bytes;
+                for sample in 0..samples_per_row {
+                    let byte_off = row_start + sample * descriptor.bytes_per_sample;
+                    let raw = u16::from_le_bytes([yuv_data[byte_off], yuv_data[byte_off + 1]]);
+                    // Right-justify the raw word into a plain 0-1023 value:
+                    // a no-op for I010's already-LSB-justified samples, a
+                    // shift-by-6 for P010's MSB-justified ones.
+                    let sample10 = raw >> descriptor.shift;
+                    let sample8 = if plane == 0 {
+                        hdr::downshift_luma_sample(sample10, color.transfer, color.range)
+                    } else {
+                        hdr::downshift_chroma_sample(sample10, color.range, force_chroma_full_range)
+                    };
+                    downshifted.push(sample8);
+                }
+            }
+            offset += stride_bytes * rows;
+        }
+
+        let eight_bit_layout = FrameLayout {
+            format: eight_bit_format,
+            plane_strides,
+            ..*layout
+        };
+
+        // `downshift_luma_sample` tonemaps and sRGB-encodes PQ/HLG luma into a
+        // full 0-255 code range, not the original stream's limited range, so
+        // the downstream matrix conversion must be told it's full-range too —
+        // otherwise it rescales already-full-range luma as if it were 16-235,
+        // reintroducing the crushed/blown-out look this path exists to avoid.
+        // `downshift_chroma_sample` above was told to rescale chroma into full
+        // range the same way, so both channels stay consistent with this.
+        let downstream_color = if force_chroma_full_range {
+            ColorInfo {
+                range: YuvRange::Full,
+                ..*color
             }
+        } else {
+            *color
+        };
+        let color = &downstream_color;
+
+        match eight_bit_format {
+            PixelFormat::Nv12 => Self::convert_biplanar(&downshifted, &eight_bit_layout, color),
+            PixelFormat::I420 => Self::convert_planar(&downshifted, &eight_bit_layout, color),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Extract the visible sub-rectangle out of a tightly-strided BGRA buffer.
+    fn crop_bgra(bgra: &[u8], coded_width: usize, visible: crate::video::VisibleRect) -> Vec<u8> {
+        let vis_x = visible.x as usize;
+        let vis_y = visible.y as usize;
+        let vis_w = visible.width as usize;
+        let vis_h = visible.height as usize;
+
+        if vis_x == 0 && vis_y == 0 && vis_w == coded_width {
+            return bgra[..vis_w * vis_h * 4].to_vec();
+        }
+
+        let mut out = vec![0u8; vis_w * vis_h * 4];
+        for row in 0..vis_h {
+            let src_off = ((vis_y + row) * coded_width + vis_x) * 4;
+            let dst_off = row * vis_w * 4;
+            out[dst_off..dst_off + vis_w * 4]
+                .copy_from_slice(&bgra[src_off..src_off + vis_w * 4]);
         }
+        out
     }
 }
 
@@ -410,30 +1059,32 @@ impl Element for VideoElement {
         cx: &mut gpui::App,
     ) {
         // FIX: Take only ONE frame, not all buffered frames
-        let frame_to_render: Option<(Vec<u8>, u32, u32)> = if self.video.buffered_len() > 0 {
-            // Pop only the NEXT frame to render
-            self.video.pop_buffered_frame()
-        } else {
-            // Fall back to current frame
-            self.video.current_frame_data()
-        };
+        let frame_to_render: Option<(Vec<u8>, FrameLayout, ColorInfo)> =
+            if self.video.buffered_len() > 0 {
+                // Pop only the NEXT frame to render
+                self.video.pop_buffered_frame()
+            } else {
+                // Fall back to current frame
+                self.video.current_frame_data()
+            };
 
-        if let Some((yuv_data, frame_width, frame_height)) = frame_to_render {
+        if let Some((yuv_data, layout, color)) = frame_to_render {
             #[cfg(target_os = "macos")]
             {
-                if self.try_paint_surface_macos(
-                    window,
-                    bounds,
-                    &yuv_data,
-                    frame_width,
-                    frame_height,
-                ) {
+                if self.try_paint_surface_macos(window, bounds, &yuv_data, &layout, &color) {
                     return;
                 }
             }
 
-            let rgb_data = self.yuv_to_rgb(&yuv_data, frame_width, frame_height);
-            self.paint_render_image(window, cx, bounds, rgb_data, frame_width, frame_height);
+            if layout.format.is_high_bit_depth()
+                && self.try_paint_half_float_hdr(window, bounds, &yuv_data, &layout, &color)
+            {
+                return;
+            }
+
+            let rgb_data = Self::yuv_to_rgb(&yuv_data, &layout, &color);
+            let visible = layout.visible_rect;
+            self.paint_render_image(window, cx, bounds, rgb_data, visible.width, visible.height);
         }
     }
 }
@@ -450,3 +1101,321 @@ impl IntoElement for VideoElement {
 pub fn video(video: Video) -> VideoElement {
     VideoElement::new(video)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::video::VisibleRect;
+
+    #[test]
+    fn extend_plane_edges_replicates_right_column_and_bottom_row() {
+        // A 2x2 visible region inside a 4x3 coded (stride 4, height 3) plane.
+        let mut plane = vec![
+            1, 2, 0, 0, //
+            3, 4, 0, 0, //
+            0, 0, 0, 0, //
+        ];
+        VideoElement::extend_plane_edges(&mut plane, 4, 3, 2, 2, 1);
+
+        assert_eq!(plane[0..4], [1, 2, 2, 2]);
+        assert_eq!(plane[4..8], [3, 4, 4, 4]);
+        // Bottom padding row replicates the (already column-extended) last
+        // visible row.
+        assert_eq!(plane[8..12], [3, 4, 4, 4]);
+    }
+
+    #[test]
+    fn extend_plane_edges_is_a_no_op_with_no_padding() {
+        let mut plane = vec![1, 2, 3, 4];
+        VideoElement::extend_plane_edges(&mut plane, 2, 2, 2, 2, 1);
+        assert_eq!(plane, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn extend_plane_edges_replicates_whole_samples_for_interleaved_chroma() {
+        // A 1-row-high, 2-sample-wide (4 bytes) visible region of interleaved
+        // U/V pairs inside a 6-byte (3-sample) stride. Single-byte
+        // replication would misalign the padding's U/V pairs; sample_size=2
+        // must copy the last (U, V) pair as a unit.
+        let mut plane = vec![10, 20, 30, 40, 0, 0];
+        VideoElement::extend_plane_edges(&mut plane, 6, 1, 4, 1, 2);
+        assert_eq!(plane, vec![10, 20, 30, 40, 30, 40]);
+    }
+
+    #[test]
+    fn extend_plane_edges_handles_padding_not_a_multiple_of_sample_size() {
+        // stride=7 leaves 3 bytes of padding past a 4-byte visible width with
+        // sample_size=2 (interleaved chroma): 3 isn't a multiple of 2, so the
+        // last whole-sample copy in each row must be truncated instead of
+        // writing past the row's end.
+        let mut plane = vec![
+            1, 2, 3, 4, 0, 0, 0, //
+            5, 6, 7, 8, 0, 0, 0, //
+        ];
+        VideoElement::extend_plane_edges(&mut plane, 7, 2, 4, 2, 2);
+        assert_eq!(plane[0..7], [1, 2, 3, 4, 3, 4, 3]);
+        assert_eq!(plane[7..14], [5, 6, 7, 8, 7, 8, 7]);
+    }
+
+    #[test]
+    fn crop_bgra_extracts_visible_subrect() {
+        // 4x2 coded BGRA buffer; crop out the 2x1 region starting at (1, 1).
+        let coded_width = 4;
+        let mut bgra = vec![0u8; coded_width * 2 * 4];
+        let row1_col1 = (1 * coded_width + 1) * 4;
+        bgra[row1_col1..row1_col1 + 8].copy_from_slice(&[10, 20, 30, 40, 50, 60, 70, 80]);
+
+        let visible = VisibleRect {
+            x: 1,
+            y: 1,
+            width: 2,
+            height: 1,
+        };
+        let cropped = VideoElement::crop_bgra(&bgra, coded_width, visible);
+        assert_eq!(cropped, vec![10, 20, 30, 40, 50, 60, 70, 80]);
+    }
+
+    #[test]
+    fn crop_bgra_fast_path_covers_full_width() {
+        let coded_width = 2;
+        let bgra = vec![1u8; coded_width * 2 * 4];
+        let visible = VisibleRect {
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 2,
+        };
+        let cropped = VideoElement::crop_bgra(&bgra, coded_width, visible);
+        assert_eq!(cropped, bgra);
+    }
+
+    #[test]
+    fn convert_high_bit_depth_downshifts_p010_and_preserves_chroma_order() {
+        // 4x2 P010 frame: Y plane (8 mid-gray 10-bit samples), then an
+        // interleaved UV plane of 2 (U, V) pairs — U held neutral, V
+        // saturated. P010 (FFmpeg's `P010LE`) is MSB-justified: each 10-bit
+        // value sits in the high 10 bits of the 16-bit word with the low 6
+        // bits zeroed, so the raw samples below are the real 10-bit value
+        // shifted left by 6, not the bare 0-1023 value I010 would use.
+        //
+        // Default colorimetry here is plain Bt.709 transfer, so both luma and
+        // chroma take the bit-shift branch (not the PQ/HLG tonemap one); a
+        // saturated Cr should still push the decoded pixel toward red and
+        // away from blue after the downshift, same as the 8-bit paths. This
+        // comparison catches a U/V order mix-up; a missing right-shift alone
+        // would truncate Y and U to 0 and V to 240, which still reads as red
+        // above blue.
+        let layout = FrameLayout::tight(PixelFormat::P010, 4, 2);
+        assert_eq!(layout.plane_strides, [8, 8, 0]);
+
+        let msb_justified = |sample10: u16| (sample10 << 6).to_le_bytes();
+
+        let mut yuv_data = Vec::new();
+        for _ in 0..8 {
+            yuv_data.extend_from_slice(&msb_justified(512)); // Y: mid-gray
+        }
+        for _ in 0..2 {
+            yuv_data.extend_from_slice(&msb_justified(512)); // U: neutral
+            yuv_data.extend_from_slice(&msb_justified(1023)); // V: saturated
+        }
+
+        let color = ColorInfo::default_for_height(480);
+        let bgra = VideoElement::convert_high_bit_depth(&yuv_data, &layout, &color);
+        assert_eq!(bgra.len(), 4 * 2 * 4);
+
+        let (blue, red) = (bgra[0], bgra[2]);
+        assert!(
+            red > blue + 50,
+            "expected saturated 10-bit V to push red above blue after downshift, got bgra={:?}",
+            &bgra[0..4]
+        );
+    }
+
+    #[test]
+    fn convert_high_bit_depth_reads_i010_samples_without_shifting() {
+        // Same pixel values as the P010 test above, but laid out the way
+        // I010 (FFmpeg's `YUV420P10LE`) actually stores them: LSB-justified,
+        // a bare 0-1023 value with no left-shift. I010 is a fully planar
+        // (3-plane) format, so this also exercises the Y/U/V plane split
+        // rather than P010's interleaved UV plane.
+        let layout = FrameLayout::tight(PixelFormat::I010, 4, 2);
+        assert_eq!(layout.plane_strides, [8, 4, 4]);
+
+        let mut yuv_data = Vec::new();
+        for _ in 0..8 {
+            yuv_data.extend_from_slice(&512u16.to_le_bytes()); // Y: mid-gray
+        }
+        for _ in 0..2 {
+            yuv_data.extend_from_slice(&512u16.to_le_bytes()); // U: neutral
+        }
+        for _ in 0..2 {
+            yuv_data.extend_from_slice(&1023u16.to_le_bytes()); // V: saturated
+        }
+
+        let color = ColorInfo::default_for_height(480);
+        let bgra = VideoElement::convert_high_bit_depth(&yuv_data, &layout, &color);
+        assert_eq!(bgra.len(), 4 * 2 * 4);
+
+        let (blue, red) = (bgra[0], bgra[2]);
+        assert!(
+            red > blue + 50,
+            "expected saturated 10-bit V to push red above blue after downshift, got bgra={:?}",
+            &bgra[0..4]
+        );
+    }
+
+    #[test]
+    fn convert_high_bit_depth_falls_back_to_black_on_truncated_buffer() {
+        // Regression coverage for the truncated-buffer panic fixed in
+        // ccea431: a decoder handing back a short P010 buffer must produce a
+        // black frame instead of indexing past the end of `yuv_data`.
+        let layout = FrameLayout::tight(PixelFormat::P010, 4, 2);
+        let short_data = vec![0u8; 4];
+
+        let color = ColorInfo::default_for_height(480);
+        let bgra = VideoElement::convert_high_bit_depth(&short_data, &layout, &color);
+        assert_eq!(bgra, vec![0u8; 4 * 2 * 4]);
+    }
+
+    #[test]
+    fn convert_biplanar_reads_nv21_chroma_in_v_then_u_order() {
+        // 4x2 NV21 buffer: Y plane (8 bytes, mid-gray), then interleaved
+        // chroma plane (4 bytes) in NV21's V-then-U order: saturated Cr (V)
+        // followed by floored Cb (U).
+        //
+        // Unlike YV12, NV21 is a real, reachable decode output
+        // (`map_pixel_format` maps ffmpeg's `Pixel::NV21` to it), so this
+        // exercises the one dispatch branch in `convert_biplanar` that a
+        // real camera/decoder can actually hit. A saturated Cr with a
+        // floored Cb pushes the decoded pixel toward red and away from blue;
+        // if the U/V bytes were read in NV12's U-then-V order instead, the
+        // same bytes would push toward blue.
+        let mut yuv_data = vec![128u8; 8];
+        yuv_data.extend_from_slice(&[255, 0, 255, 0]); // interleaved (V, U) pairs
+
+        let layout = FrameLayout::tight(PixelFormat::Nv21, 4, 2);
+        assert_eq!(layout.plane_strides[0], 4);
+        let color = ColorInfo::default_for_height(480);
+
+        let bgra = VideoElement::convert_biplanar(&yuv_data, &layout, &color);
+        assert_eq!(bgra.len(), 4 * 2 * 4);
+
+        let (blue, red) = (bgra[0], bgra[2]);
+        assert!(
+            red > blue + 50,
+            "expected saturated V to read as Cr and push red above blue, got bgra={:?}",
+            &bgra[0..4]
+        );
+    }
+
+    #[test]
+    fn convert_biplanar_respects_padded_stride_when_cropping_to_visible_rect() {
+        // A 6-wide coded NV12 buffer holding a 4-wide visible frame — the
+        // stride-vs-width distinction the other chroma-order tests in this
+        // file don't exercise, and exactly where using the visible width in
+        // place of `plane_strides` would misalign every row after the first
+        // and misplace the chroma plane behind them.
+        let coded_width = 6u32;
+        let coded_height = 2u32;
+        let y_stride = 6;
+        let uv_stride = 6;
+
+        // Row 0 visible samples are mid-gray, row 1 visible samples are
+        // floored; the padding columns are garbage that a correct
+        // stride-aware read must crop away rather than let bleed into the
+        // visible output.
+        let y_plane = vec![128u8, 128, 128, 128, 9, 9, 0u8, 0, 0, 0, 9, 9];
+        assert_eq!(y_plane.len(), y_stride * coded_height as usize);
+
+        // Interleaved UV, one subsampled row: saturated V for the 2 visible
+        // chroma samples (covering the 4-wide visible luma), garbage U/V in
+        // the padding past them.
+        let uv_plane = vec![128u8, 255, 128, 255, 9, 9];
+        assert_eq!(uv_plane.len(), uv_stride);
+
+        let mut yuv_data = y_plane;
+        yuv_data.extend_from_slice(&uv_plane);
+
+        let layout = FrameLayout {
+            format: PixelFormat::Nv12,
+            coded_width,
+            coded_height,
+            visible_rect: VisibleRect {
+                x: 0,
+                y: 0,
+                width: 4,
+                height: 2,
+            },
+            plane_strides: [y_stride, uv_stride, 0],
+        };
+        let color = ColorInfo::default_for_height(480);
+
+        let bgra = VideoElement::convert_biplanar(&yuv_data, &layout, &color);
+        assert_eq!(bgra.len(), 4 * 2 * 4);
+
+        let (blue, red) = (bgra[0], bgra[2]);
+        assert!(
+            red > blue + 50,
+            "expected saturated V in the first visible chroma column to push red \
+             above blue after a stride-aware crop, got bgra={:?}",
+            &bgra[0..4]
+        );
+    }
+
+    // YV12 is never actually produced by `map_pixel_format` (ffmpeg has no
+    // native YV12 `Pixel` variant), so these are the only tests that ever
+    // exercise its plane-order handling. Feed synthetic bytes directly
+    // rather than relying on an end-to-end decode that can't happen.
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn planar_chroma_offsets_swaps_u_and_v_for_yv12() {
+        let y_size = 8;
+        let plane_strides = [4, 2, 2];
+        let chroma_h = 1;
+
+        let (i420_u_off, i420_u_stride, i420_v_off, i420_v_stride) =
+            VideoElement::planar_chroma_offsets(PixelFormat::I420, y_size, plane_strides, chroma_h);
+        assert_eq!((i420_u_off, i420_u_stride), (y_size, 2));
+        assert_eq!((i420_v_off, i420_v_stride), (y_size + 2, 2));
+
+        let (yv12_u_off, yv12_u_stride, yv12_v_off, yv12_v_stride) =
+            VideoElement::planar_chroma_offsets(PixelFormat::Yv12, y_size, plane_strides, chroma_h);
+        // YV12's physical plane order is Y, V, U, so the logical U offset
+        // lands where I420's logical V offset did, and vice versa.
+        assert_eq!((yv12_u_off, yv12_u_stride), (y_size + 2, 2));
+        assert_eq!((yv12_v_off, yv12_v_stride), (y_size, 2));
+    }
+
+    #[test]
+    fn convert_planar_reads_yv12_chroma_in_v_then_u_order() {
+        // 4x2 YV12 buffer: Y plane (8 bytes, mid-gray), then V plane (2
+        // bytes, maxed out), then U plane (2 bytes, zeroed), matching
+        // `FrameLayout::tight`'s descriptor-derived strides for a 3-plane
+        // 4:2:0 format.
+        //
+        // A saturated Cr (V) with a floored Cb (U) pushes the decoded pixel
+        // strongly toward red and away from blue. If the physical V/U planes
+        // were swapped (treated as U-then-V like I420 instead of YV12's
+        // V-then-U), the same bytes would push toward blue instead — so
+        // comparing the red and blue output channels catches a plane-order
+        // regression that a bare output-length check cannot.
+        let mut yuv_data = vec![128u8; 8];
+        yuv_data.extend_from_slice(&[255, 255]); // V plane
+        yuv_data.extend_from_slice(&[0, 0]); // U plane
+
+        let layout = FrameLayout::tight(PixelFormat::Yv12, 4, 2);
+        assert_eq!(layout.plane_strides, [4, 2, 2]);
+        let color = ColorInfo::default_for_height(480);
+
+        let bgra = VideoElement::convert_planar(&yuv_data, &layout, &color);
+        assert_eq!(bgra.len(), 4 * 2 * 4);
+
+        let (blue, red) = (bgra[0], bgra[2]);
+        assert!(
+            red > blue + 50,
+            "expected saturated V to read as Cr and push red above blue, got bgra={:?}",
+            &bgra[0..4]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::color::ColorTransfer;
+use yuv::YuvRange;
+
+/// SMPTE ST 2084 (PQ) EOTF: maps a normalized [0, 1] PQ code value to linear
+/// light, normalized so that 1.0 corresponds to the PQ reference white of
+/// 10,000 nits.
+pub fn pq_eotf(code: f32) -> f32 {
+    const M1: f32 = 2610.0 / 16384.0;
+    const M2: f32 = 2523.0 / 4096.0 * 128.0;
+    const C1: f32 = 3424.0 / 4096.0;
+    const C2: f32 = 2413.0 / 4096.0 * 32.0;
+    const C3: f32 = 2392.0 / 4096.0 * 32.0;
+
+    let code = code.clamp(0.0, 1.0);
+    let num = (code.powf(1.0 / M2) - C1).max(0.0);
+    let den = C2 - C3 * code.powf(1.0 / M2);
+    (num / den).powf(1.0 / M1)
+}
+
+/// ARIB STD-B67 (HLG) inverse OETF: maps a normalized [0, 1] HLG code value
+/// to scene-linear light.
+pub fn hlg_eotf(code: f32) -> f32 {
+    const A: f32 = 0.17883277;
+    const B: f32 = 1.0 - 4.0 * A;
+    const C: f32 = 0.5 - A * (4.0 * A).ln();
+
+    let code = code.clamp(0.0, 1.0);
+    if code <= 0.5 {
+        (code * code) / 3.0
+    } else {
+        (((code - C) / A).exp() + B) / 12.0
+    }
+}
+
+/// Rough Reinhard tonemap from HDR scene-linear light down to display-linear,
+/// then sRGB-encode to an 8-bit code value. This is not a perceptually
+/// accurate HDR->SDR pipeline, but it avoids the blown highlights and crushed
+/// shadows a naive bit-shift produces on PQ/HLG content.
+fn tonemap_and_encode(linear: f32) -> u8 {
+    let mapped = linear / (1.0 + linear);
+    let encoded = if mapped <= 0.003_130_8 {
+        12.92 * mapped
+    } else {
+        1.055 * mapped.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Downshift a single 10-bit luma sample to 8 bits, routing PQ/HLG content
+/// through its EOTF and a tonemap first so HDR10/HLG streams don't land on
+/// an SDR gamma curve as if they were ordinary Bt.709 content.
+///
+/// HDR10/HLG streams are almost always muxed limited-range, where the 10-bit
+/// luma excursion is `[64, 940]` (BT.2100) rather than the full `[0, 1023]`
+/// code range. The EOTF curves expect a normalized `[0, 1]` input measured
+/// against the signal's real excursion, so a limited-range sample is expanded
+/// out to `[0, 1]` before being handed to `pq_eotf`/`hlg_eotf` — otherwise
+/// true black (raw `64`) reads as `~0.06` instead of `0.0` and lifts, while
+/// peak white (raw `940`) reads as `~0.92` instead of `1.0` and crushes.
+pub fn downshift_luma_sample(sample10: u16, transfer: ColorTransfer, range: YuvRange) -> u8 {
+    match transfer {
+        ColorTransfer::Pq | ColorTransfer::Hlg => {
+            // BT.2100 10-bit limited-range luma excursion.
+            const LIMITED_LOW: f32 = 64.0;
+            const LIMITED_HIGH: f32 = 940.0;
+            let normalized = match range {
+                YuvRange::Limited => {
+                    ((sample10 as f32 - LIMITED_LOW) / (LIMITED_HIGH - LIMITED_LOW)).clamp(0.0, 1.0)
+                }
+                YuvRange::Full => sample10 as f32 / 1023.0,
+            };
+            let eotf = if transfer == ColorTransfer::Pq {
+                pq_eotf(normalized)
+            } else {
+                hlg_eotf(normalized)
+            };
+            tonemap_and_encode(eotf)
+        }
+        ColorTransfer::Bt709 | ColorTransfer::Srgb => (sample10 >> 2) as u8,
+    }
+}
+
+/// Downshift a single 10-bit chroma sample to 8 bits, optionally rescaling it
+/// into full range on the way down.
+///
+/// A plain `sample10 >> 2` bit-shift preserves whatever range the 10-bit
+/// sample was already in: BT.2100 limited-range chroma spans `[64, 960]`,
+/// which shifts down to the matching 8-bit limited span `[16, 240]`. That's
+/// correct as long as the downstream matrix conversion is told the result is
+/// still limited range. But `downshift_luma_sample` re-encodes PQ/HLG luma
+/// into a genuinely full `[0, 255]` range, and callers that do that must tell
+/// the matrix conversion the *whole frame* is full range — so when
+/// `force_full_range` is set, a limited-range chroma sample has to be
+/// rescaled the same way luma was, or it ends up interpreted against the
+/// wrong excursion and under-expands toward gray.
+pub fn downshift_chroma_sample(sample10: u16, range: YuvRange, force_full_range: bool) -> u8 {
+    if !force_full_range || range == YuvRange::Full {
+        return (sample10 >> 2) as u8;
+    }
+
+    // BT.2100 10-bit limited-range chroma excursion, centered on 512.
+    const LIMITED_LOW: f32 = 64.0;
+    const LIMITED_HIGH: f32 = 960.0;
+    let scaled = (sample10 as f32 - LIMITED_LOW) * 255.0 / (LIMITED_HIGH - LIMITED_LOW);
+    scaled.round().clamp(0.0, 255.0) as u8
+}
+
+/// Whether the current platform backend can allocate half-float (RGBA16F)
+/// textures, which would let 10-bit content upload without any precision
+/// loss at all. `gpui` does not expose that allocation through its public
+/// API yet (tracked against freesurfacemodules/gpui-video#chunk0-5), so this
+/// stays `false` everywhere and callers fall back to 8-bit downshifting via
+/// `downshift_luma_sample`/`downshift_chroma_sample`.
+pub fn half_float_textures_available() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pq_eotf_is_monotonic_and_bounded() {
+        assert_eq!(pq_eotf(0.0), 0.0);
+        assert!(pq_eotf(1.0) > pq_eotf(0.5));
+        assert!(pq_eotf(0.5) > pq_eotf(0.0));
+        assert!(pq_eotf(1.0) <= 1.0);
+    }
+
+    #[test]
+    fn hlg_eotf_matches_piecewise_definition_at_the_midpoint() {
+        // At the code = 0.5 boundary the square-law branch applies: 0.5² / 3.
+        let below = 0.5 * 0.5 / 3.0;
+        assert!((hlg_eotf(0.5) - below).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hlg_eotf_is_monotonic() {
+        assert!(hlg_eotf(1.0) > hlg_eotf(0.75));
+        assert!(hlg_eotf(0.75) > hlg_eotf(0.25));
+    }
+
+    #[test]
+    fn downshift_sdr_transfer_is_a_plain_bit_shift() {
+        assert_eq!(
+            downshift_luma_sample(0b11_1111_1111, ColorTransfer::Bt709, YuvRange::Full),
+            0xFF
+        );
+        assert_eq!(downshift_luma_sample(0, ColorTransfer::Srgb, YuvRange::Full), 0);
+        assert_eq!(downshift_luma_sample(4, ColorTransfer::Bt709, YuvRange::Full), 1);
+        // The bit-shift path preserves whatever range the sample was already
+        // in (same as `downshift_chroma_sample` without `force_full_range`),
+        // so a limited-range SDR sample shifts the same as a full-range one.
+        assert_eq!(
+            downshift_luma_sample(0b11_1111_1111, ColorTransfer::Bt709, YuvRange::Limited),
+            0xFF
+        );
+    }
+
+    #[test]
+    fn downshift_pq_and_hlg_stay_in_range_and_dont_crush_black() {
+        for transfer in [ColorTransfer::Pq, ColorTransfer::Hlg] {
+            assert_eq!(downshift_luma_sample(0, transfer, YuvRange::Full), 0);
+            let mid = downshift_luma_sample(512, transfer, YuvRange::Full);
+            let high = downshift_luma_sample(1023, transfer, YuvRange::Full);
+            assert!(mid > 0, "{transfer:?} mid-tone sample downshifted to black");
+            assert!(high >= mid, "{transfer:?} downshift should be monotonic");
+        }
+    }
+
+    #[test]
+    fn downshift_pq_hlg_expands_limited_range_luma_before_the_eotf() {
+        // Raw 64 is true black and raw 940 is peak white in BT.2100
+        // limited-range luma; fed through untouched (as if full-range) they'd
+        // read as ~0.06 and ~0.92 instead, lifting black and crushing white.
+        for transfer in [ColorTransfer::Pq, ColorTransfer::Hlg] {
+            assert_eq!(
+                downshift_luma_sample(64, transfer, YuvRange::Limited),
+                0,
+                "{transfer:?} limited-range true black should downshift to 0"
+            );
+            let limited_white = downshift_luma_sample(940, transfer, YuvRange::Limited);
+            let full_white = downshift_luma_sample(940, transfer, YuvRange::Full);
+            assert!(
+                limited_white > full_white,
+                "{transfer:?} treating a limited-range sample as full-range should crush \
+                 peak white relative to the correctly-expanded result (limited={limited_white}, \
+                 full={full_white})"
+            );
+        }
+    }
+
+    #[test]
+    fn downshift_chroma_without_force_is_a_plain_bit_shift() {
+        assert_eq!(downshift_chroma_sample(0b11_1111_1111, YuvRange::Limited, false), 0xFF);
+        // Full-range input also takes the plain shift, even with the force flag set.
+        assert_eq!(downshift_chroma_sample(512, YuvRange::Full, true), 128);
+    }
+
+    #[test]
+    fn downshift_chroma_forced_full_range_rescales_limited_excursion() {
+        // BT.2100 limited-range chroma: 64 is the bottom of the excursion, 512
+        // is dead center (neutral chroma), 960 is the top.
+        assert_eq!(downshift_chroma_sample(64, YuvRange::Limited, true), 0);
+        assert_eq!(downshift_chroma_sample(512, YuvRange::Limited, true), 128);
+        assert_eq!(downshift_chroma_sample(960, YuvRange::Limited, true), 255);
+    }
+}
@@ -0,0 +1,10 @@
+mod color;
+mod element;
+mod hdr;
+mod pixel_format;
+mod video;
+
+pub use color::{ColorInfo, ColorPrimaries, ColorTransfer};
+pub use element::{video, VideoElement};
+pub use pixel_format::{PixelFormat, PixelFormatDescriptor};
+pub use video::{FrameLayout, Video, VideoError, VisibleRect};
@@ -0,0 +1,97 @@
+/// Planar/bi-planar pixel formats `Video` may hand back decoded frames in.
+///
+/// Hardware decoders on different platforms settle on different native
+/// layouts (Apple favors NV12, many Android/software decoders emit I420),
+/// and high-bit-depth content needs a 10-bit-in-16-bit variant of each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 4:2:0, one Y plane, one interleaved UV plane.
+    Nv12,
+    /// 4:2:0, one Y plane, one interleaved VU plane.
+    Nv21,
+    /// 4:2:0, three planes in Y, U, V order.
+    I420,
+    /// 4:2:0, three planes in Y, V, U order.
+    Yv12,
+    /// 10-bit 4:2:0, NV12 layout with each sample widened to 16 bits.
+    P010,
+    /// 10-bit 4:2:0, I420 layout with each sample widened to 16 bits.
+    I010,
+}
+
+/// Static description of a `PixelFormat`'s memory layout, used to drive plane
+/// splitting, chroma upsampling, and dispatch to the matching conversion
+/// routine without hardcoding NV12 assumptions throughout the element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelFormatDescriptor {
+    /// Number of distinct planes (2 for bi-planar formats, 3 for fully planar).
+    pub plane_count: usize,
+    /// Components packed per sample in each plane (e.g. NV12's chroma plane
+    /// interleaves 2 components per sample; unused planes are 0).
+    pub components_per_plane: [usize; 3],
+    /// Chroma subsampling factor relative to luma, per axis.
+    pub horizontal_subsampling: u32,
+    pub vertical_subsampling: u32,
+    /// Bits of actual sample precision (8 for the byte formats, 10 for P010/I010).
+    pub bits_per_component: u32,
+    /// Byte size of a single raw sample: 1 for 8-bit formats, 2 for the
+    /// 10-bit formats.
+    pub bytes_per_sample: usize,
+    /// Right shift needed to turn a raw little-endian sample into its true
+    /// `bits_per_component`-wide value. I010 (FFmpeg's `YUV420P10LE`) is
+    /// LSB-justified, so its samples are already a plain 0-1023 value and
+    /// this is 0. P010 (`P010LE`) is MSB-justified instead — each 10-bit
+    /// sample sits in the high bits of the 16-bit word with the low 6 bits
+    /// zeroed — so it needs shifting right by 6 before it's a 0-1023 value.
+    pub shift: u32,
+}
+
+impl PixelFormat {
+    /// Look up this format's static layout descriptor.
+    pub const fn descriptor(self) -> PixelFormatDescriptor {
+        use PixelFormat::*;
+        match self {
+            Nv12 | Nv21 => PixelFormatDescriptor {
+                plane_count: 2,
+                components_per_plane: [1, 2, 0],
+                horizontal_subsampling: 2,
+                vertical_subsampling: 2,
+                bits_per_component: 8,
+                bytes_per_sample: 1,
+                shift: 0,
+            },
+            I420 | Yv12 => PixelFormatDescriptor {
+                plane_count: 3,
+                components_per_plane: [1, 1, 1],
+                horizontal_subsampling: 2,
+                vertical_subsampling: 2,
+                bits_per_component: 8,
+                bytes_per_sample: 1,
+                shift: 0,
+            },
+            P010 => PixelFormatDescriptor {
+                plane_count: 2,
+                components_per_plane: [1, 2, 0],
+                horizontal_subsampling: 2,
+                vertical_subsampling: 2,
+                bits_per_component: 10,
+                bytes_per_sample: 2,
+                shift: 6,
+            },
+            I010 => PixelFormatDescriptor {
+                plane_count: 3,
+                components_per_plane: [1, 1, 1],
+                horizontal_subsampling: 2,
+                vertical_subsampling: 2,
+                bits_per_component: 10,
+                bytes_per_sample: 2,
+                shift: 0,
+            },
+        }
+    }
+
+    /// Whether this format packs more than 8 bits of precision per component.
+    pub const fn is_high_bit_depth(self) -> bool {
+        self.descriptor().bits_per_component > 8
+    }
+}
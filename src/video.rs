@@ -0,0 +1,401 @@
+use crate::color::{ColorInfo, ColorPrimaries, ColorTransfer};
+use crate::pixel_format::PixelFormat;
+use ffmpeg_next::{self as ffmpeg, format::Pixel, media::Type};
+use ffmpeg_next::util::color::{Primaries, Range, Space, TransferCharacteristic};
+use yuv::{YuvRange, YuvStandardMatrix};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use url::Url;
+
+/// The sub-rectangle of a decoded buffer that should actually be displayed.
+///
+/// Hardware decoders commonly hand back a buffer padded to an alignment boundary
+/// (e.g. a 1920x1080 stream decoded into a 1920x1088 surface); `visible_rect` is
+/// the region within that padded buffer that the demuxer says is meaningful.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisibleRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Describes the memory layout of a decoded frame: how large the underlying
+/// (possibly padded) buffer is, which part of it is meant to be shown, and the
+/// byte stride of each plane as actually produced by the decoder.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameLayout {
+    /// Which planar/bi-planar layout the plane bytes below are arranged as.
+    pub format: PixelFormat,
+    /// Full width/height of the coded buffer, including any decoder padding.
+    pub coded_width: u32,
+    pub coded_height: u32,
+    /// Region of the coded buffer that should be displayed.
+    pub visible_rect: VisibleRect,
+    /// Byte stride of each plane, in decode order. Unused planes are left at 0.
+    pub plane_strides: [usize; 3],
+}
+
+impl FrameLayout {
+    /// A layout with no padding: every plane's stride is computed straight
+    /// from the format's descriptor (accounting for chroma subsampling and
+    /// sample width) and the entire coded buffer is visible.
+    pub fn tight(format: PixelFormat, width: u32, height: u32) -> Self {
+        let descriptor = format.descriptor();
+        let mut plane_strides = [0usize; 3];
+        for (plane, stride) in plane_strides.iter_mut().enumerate().take(descriptor.plane_count) {
+            let sample_width = if plane == 0 {
+                width as usize
+            } else {
+                width as usize / descriptor.horizontal_subsampling as usize
+            };
+            let components = if plane == 0 {
+                1
+            } else {
+                descriptor.components_per_plane[plane]
+            };
+            *stride = sample_width * components * descriptor.bytes_per_sample;
+        }
+
+        Self {
+            format,
+            coded_width: width,
+            coded_height: height,
+            visible_rect: VisibleRect {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            },
+            plane_strides,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VideoError {
+    #[error("failed to open video source: {0}")]
+    Open(#[from] ffmpeg::Error),
+    #[error("no video stream found in source")]
+    NoVideoStream,
+}
+
+struct DecodedFrame {
+    data: Vec<u8>,
+    layout: FrameLayout,
+}
+
+struct Shared {
+    buffer: VecDeque<DecodedFrame>,
+    current: Option<DecodedFrame>,
+    capacity: usize,
+    paused: bool,
+    eos: bool,
+    frame_ready: bool,
+    display_width: u32,
+    display_height: u32,
+    color: ColorInfo,
+}
+
+/// Handle to a decoding video stream, cheaply cloneable and shared between the
+/// playback controller and any number of `VideoElement`s painting it.
+#[derive(Clone)]
+pub struct Video {
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Video {
+    pub fn new(uri: &Url) -> Result<Self, VideoError> {
+        let path = uri
+            .to_file_path()
+            .unwrap_or_else(|_| uri.as_str().into());
+
+        let input = ffmpeg::format::input(&path)?;
+        let stream = input
+            .streams()
+            .best(Type::Video)
+            .ok_or(VideoError::NoVideoStream)?;
+        let stream_index = stream.index();
+        let decoder = ffmpeg::codec::context::Context::from_parameters(stream.parameters())?
+            .decoder()
+            .video()?;
+
+        let color = extract_color_info(&decoder);
+
+        let shared = Arc::new(Mutex::new(Shared {
+            buffer: VecDeque::new(),
+            current: None,
+            capacity: 1,
+            paused: false,
+            eos: false,
+            frame_ready: false,
+            display_width: decoder.width(),
+            display_height: decoder.height(),
+            color,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        spawn_decode_thread(input, stream_index, decoder, shared.clone(), stop.clone());
+
+        Ok(Self { shared, stop })
+    }
+
+    /// Current effective display size, derived from the decoder's reported
+    /// visible dimensions (not the padded coded size).
+    pub fn display_size(&self) -> (u32, u32) {
+        let shared = self.shared.lock().unwrap();
+        (shared.display_width, shared.display_height)
+    }
+
+    /// Configure how many decoded frames may sit in the buffer ahead of the
+    /// one currently being displayed. 0 is treated like 1: only the newest
+    /// decoded frame is kept.
+    pub fn set_frame_buffer_capacity(&self, capacity: usize) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.capacity = capacity;
+        while shared.buffer.len() > capacity.max(1) {
+            shared.buffer.pop_front();
+        }
+    }
+
+    pub fn eos(&self) -> bool {
+        self.shared.lock().unwrap().eos
+    }
+
+    pub fn paused(&self) -> bool {
+        self.shared.lock().unwrap().paused
+    }
+
+    /// Returns true and clears the flag if a new frame has arrived since the
+    /// last call, so callers can decide whether a repaint is warranted.
+    pub fn take_frame_ready(&self) -> bool {
+        let mut shared = self.shared.lock().unwrap();
+        std::mem::take(&mut shared.frame_ready)
+    }
+
+    pub fn buffered_len(&self) -> usize {
+        self.shared.lock().unwrap().buffer.len()
+    }
+
+    /// Pop the next buffered frame (in presentation order), becoming the new
+    /// "current" frame for subsequent calls to `current_frame_data`.
+    pub fn pop_buffered_frame(&self) -> Option<(Vec<u8>, FrameLayout, ColorInfo)> {
+        let mut shared = self.shared.lock().unwrap();
+        let frame = shared.buffer.pop_front()?;
+        let result = (frame.data.clone(), frame.layout, shared.color);
+        shared.current = Some(frame);
+        Some(result)
+    }
+
+    /// The most recently displayed frame, without advancing the buffer.
+    pub fn current_frame_data(&self) -> Option<(Vec<u8>, FrameLayout, ColorInfo)> {
+        let shared = self.shared.lock().unwrap();
+        let color = shared.color;
+        shared
+            .current
+            .as_ref()
+            .map(|frame| (frame.data.clone(), frame.layout, color))
+    }
+
+    /// Colorimetry (range/matrix/transfer/primaries) of the stream, as
+    /// extracted from its metadata (or a sensible default when absent).
+    pub fn color_info(&self) -> ColorInfo {
+        self.shared.lock().unwrap().color
+    }
+}
+
+impl Drop for Video {
+    fn drop(&mut self) {
+        // Only the last handle owning the decode thread should stop it. The
+        // decode thread holds its own clone of `shared`, so a count of 2 (or
+        // 1 once the thread has exited) means no other `Video` handle is left.
+        if Arc::strong_count(&self.shared) <= 2 {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+fn spawn_decode_thread(
+    mut input: ffmpeg::format::context::Input,
+    stream_index: usize,
+    mut decoder: ffmpeg::decoder::Video,
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+) {
+    thread::spawn(move || {
+        let mut frame = ffmpeg::util::frame::Video::empty();
+
+        'decode: for (stream, packet) in input.packets() {
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            if stream.index() != stream_index {
+                continue;
+            }
+            if decoder.send_packet(&packet).is_err() {
+                continue;
+            }
+            while decoder.receive_frame(&mut frame).is_ok() {
+                // Read the coded size fresh per frame rather than once at
+                // stream open: VP9/AV1 and a few other codecs this crate
+                // targets support dynamic resolution changes mid-stream, so
+                // the decoder's picture buffer size can change between
+                // `receive_frame` calls.
+                let coded_width = decoder.coded_width();
+                let coded_height = decoder.coded_height();
+                if let Some(decoded) = extract_frame(&frame, coded_width, coded_height) {
+                    let mut shared = shared.lock().unwrap();
+                    let capacity = shared.capacity.max(1);
+                    while shared.buffer.len() >= capacity {
+                        shared.buffer.pop_front();
+                    }
+                    shared.buffer.push_back(decoded);
+                    shared.frame_ready = true;
+                }
+                if stop.load(Ordering::Relaxed) {
+                    break 'decode;
+                }
+            }
+        }
+
+        shared.lock().unwrap().eos = true;
+    });
+}
+
+/// Extract colorimetry from the demuxed stream's metadata, falling back to
+/// the height-based default for anything the stream leaves unspecified.
+fn extract_color_info(decoder: &ffmpeg::decoder::Video) -> ColorInfo {
+    let default = ColorInfo::default_for_height(decoder.height());
+
+    let range = match decoder.color_range() {
+        Range::MPEG => YuvRange::Limited,
+        Range::JPEG => YuvRange::Full,
+        _ => default.range,
+    };
+    let matrix = match decoder.color_space() {
+        Space::BT709 => YuvStandardMatrix::Bt709,
+        Space::BT470BG | Space::SMPTE170M => YuvStandardMatrix::Bt601,
+        Space::BT2020NCL | Space::BT2020CL => YuvStandardMatrix::Bt2020,
+        _ => default.matrix,
+    };
+    let primaries = match decoder.color_primaries() {
+        Primaries::BT709 => ColorPrimaries::Bt709,
+        Primaries::BT470BG | Primaries::SMPTE170M => ColorPrimaries::Bt601,
+        Primaries::BT2020 => ColorPrimaries::Bt2020,
+        _ => default.primaries,
+    };
+    let transfer = match decoder.color_transfer_characteristic() {
+        TransferCharacteristic::BT709 => ColorTransfer::Bt709,
+        TransferCharacteristic::IEC61966_2_1 => ColorTransfer::Srgb,
+        TransferCharacteristic::SMPTE2084 => ColorTransfer::Pq,
+        TransferCharacteristic::ARIB_STD_B67 => ColorTransfer::Hlg,
+        _ => default.transfer,
+    };
+
+    ColorInfo {
+        range,
+        matrix,
+        transfer,
+        primaries,
+    }
+}
+
+/// Map an ffmpeg pixel format to our own `PixelFormat`, the set this crate
+/// knows how to convert and/or zero-copy present.
+///
+/// Note: ffmpeg has no native `Pixel::YV12` variant, so `PixelFormat::Yv12`
+/// is never actually produced by this function — its conversion and
+/// zero-copy paths exist ahead of this crate's only frame source for
+/// decoders/wrappers that do hand back YV12 directly (it's a common enough
+/// layout, e.g. from some software scalers), and are covered by unit tests
+/// feeding them synthetic YV12 bytes directly rather than an end-to-end path.
+fn map_pixel_format(format: Pixel) -> Option<PixelFormat> {
+    match format {
+        Pixel::NV12 => Some(PixelFormat::Nv12),
+        Pixel::NV21 => Some(PixelFormat::Nv21),
+        Pixel::YUV420P => Some(PixelFormat::I420),
+        Pixel::YUVJ420P => Some(PixelFormat::I420),
+        Pixel::YUV420P10LE => Some(PixelFormat::I010),
+        Pixel::P010LE => Some(PixelFormat::P010),
+        _ => None,
+    }
+}
+
+/// Copy a decoded frame's planes into a single contiguous buffer, preserving
+/// the decoder's real strides and visible rect rather than assuming tight
+/// packing or a single hardcoded format.
+///
+/// `coded_width`/`coded_height` are the codec's padded picture buffer size
+/// (`AVCodecContext::coded_{width,height}`), which is what the plane data is
+/// actually allocated and laid out at; the frame's own `width()`/`height()`
+/// are the stream's real, potentially smaller, display size cropped out of
+/// that buffer.
+fn extract_frame(
+    frame: &ffmpeg::util::frame::Video,
+    coded_width: u32,
+    coded_height: u32,
+) -> Option<DecodedFrame> {
+    let format = map_pixel_format(frame.format())?;
+    let descriptor = format.descriptor();
+
+    let width = frame.width();
+    let height = frame.height();
+    // Codecs never pad *below* the display size; guard against a decoder
+    // that reports 0 (meaning "unknown") by falling back to the tight case.
+    let coded_width = coded_width.max(width);
+    let coded_height = coded_height.max(height);
+    let chroma_rows = coded_height as usize / descriptor.vertical_subsampling as usize;
+
+    let mut plane_strides = [0usize; 3];
+    let mut data = Vec::new();
+    for plane in 0..descriptor.plane_count {
+        let stride = frame.stride(plane);
+        let rows = if plane == 0 { coded_height as usize } else { chroma_rows };
+        plane_strides[plane] = stride;
+        data.extend_from_slice(&frame.data(plane)[..stride * rows]);
+    }
+
+    let layout = FrameLayout {
+        format,
+        coded_width,
+        coded_height,
+        visible_rect: VisibleRect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        },
+        plane_strides,
+    };
+
+    Some(DecodedFrame { data, layout })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tight_layout_has_no_padding() {
+        let layout = FrameLayout::tight(PixelFormat::Nv12, 16, 8);
+        assert_eq!(layout.coded_width, layout.visible_rect.width);
+        assert_eq!(layout.coded_height, layout.visible_rect.height);
+        assert_eq!(layout.plane_strides[0], 16);
+    }
+
+    #[test]
+    fn default_for_height_picks_bt709_for_hd() {
+        let color = ColorInfo::default_for_height(1080);
+        assert_eq!(color.matrix, YuvStandardMatrix::Bt709);
+        assert_eq!(color.range, YuvRange::Limited);
+    }
+
+    #[test]
+    fn default_for_height_picks_bt601_for_sd() {
+        let color = ColorInfo::default_for_height(480);
+        assert_eq!(color.matrix, YuvStandardMatrix::Bt601);
+    }
+}